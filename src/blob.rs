@@ -0,0 +1,240 @@
+//! A high-level, generic representation of NBT data.
+
+use std::io::{Read, Write};
+
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+use flate2::Compression as Flate2Compression;
+
+use error::{Error, Result};
+use raw::{self, Endian, Limits};
+
+const TAG_END: u8 = 0x00;
+const TAG_BYTE: u8 = 0x01;
+const TAG_SHORT: u8 = 0x02;
+const TAG_INT: u8 = 0x03;
+const TAG_LONG: u8 = 0x04;
+const TAG_FLOAT: u8 = 0x05;
+const TAG_DOUBLE: u8 = 0x06;
+const TAG_BYTE_ARRAY: u8 = 0x07;
+const TAG_STRING: u8 = 0x08;
+const TAG_COMPOUND: u8 = 0x0A;
+const TAG_INT_ARRAY: u8 = 0x0B;
+const TAG_LONG_ARRAY: u8 = 0x0C;
+
+/// The compression, if any, wrapping an NBT document on disk.
+///
+/// Java-edition `.dat` files are gzipped; region-file chunk payloads are
+/// zlib-deflated; some callers hand over an already-decompressed stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Uncompressed,
+    Gzip,
+    Zlib,
+}
+
+/// A single NBT value, tagged by its NBT type.
+///
+/// Note: `TAG_Compound` and `TAG_List` (nested/heterogeneous values) are not
+/// represented here -- `Blob` only models the single level of scalar and
+/// array fields directly inside its root compound.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl Value {
+    fn tag_id(&self) -> u8 {
+        match *self {
+            Value::Byte(_) => TAG_BYTE,
+            Value::Short(_) => TAG_SHORT,
+            Value::Int(_) => TAG_INT,
+            Value::Long(_) => TAG_LONG,
+            Value::Float(_) => TAG_FLOAT,
+            Value::Double(_) => TAG_DOUBLE,
+            Value::ByteArray(_) => TAG_BYTE_ARRAY,
+            Value::String(_) => TAG_STRING,
+            Value::IntArray(_) => TAG_INT_ARRAY,
+            Value::LongArray(_) => TAG_LONG_ARRAY,
+        }
+    }
+
+    fn write<W: Write>(&self, dst: &mut W, endian: Endian) -> Result<()> {
+        match *self {
+            Value::Byte(v) => raw::write_bare_byte(dst, v),
+            Value::Short(v) => raw::write_bare_short(dst, v, endian),
+            Value::Int(v) => raw::write_bare_int(dst, v, endian),
+            Value::Long(v) => raw::write_bare_long(dst, v, endian),
+            Value::Float(v) => raw::write_bare_float(dst, v, endian),
+            Value::Double(v) => raw::write_bare_double(dst, v, endian),
+            Value::ByteArray(ref v) => raw::write_bare_byte_array(dst, v, endian),
+            Value::String(ref v) => raw::write_bare_string(dst, v, endian),
+            Value::IntArray(ref v) => raw::write_bare_int_array(dst, v, endian),
+            Value::LongArray(ref v) => raw::write_bare_long_array(dst, v, endian),
+        }
+    }
+
+    fn read<R: Read>(tag: u8, src: &mut R, endian: Endian, limits: &Limits, total_read: &mut usize) -> Result<Value> {
+        match tag {
+            TAG_BYTE => raw::read_bare_byte(src).map(Value::Byte),
+            TAG_SHORT => raw::read_bare_short(src, endian).map(Value::Short),
+            TAG_INT => raw::read_bare_int(src, endian).map(Value::Int),
+            TAG_LONG => raw::read_bare_long(src, endian).map(Value::Long),
+            TAG_FLOAT => raw::read_bare_float(src, endian).map(Value::Float),
+            TAG_DOUBLE => raw::read_bare_double(src, endian).map(Value::Double),
+            TAG_BYTE_ARRAY => raw::read_bare_byte_array(src, endian, limits, total_read).map(Value::ByteArray),
+            TAG_STRING => raw::read_bare_string(src, endian, limits, total_read).map(Value::String),
+            TAG_INT_ARRAY => raw::read_bare_int_array(src, endian, limits, total_read).map(Value::IntArray),
+            TAG_LONG_ARRAY => raw::read_bare_long_array(src, endian, limits, total_read).map(Value::LongArray),
+            _ => Err(Error::InvalidTypeId(tag)),
+        }
+    }
+}
+
+/// A named, flat collection of NBT fields -- the root `TAG_Compound` of an
+/// NBT document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Blob {
+    name: String,
+    fields: Vec<(String, Value)>,
+}
+
+impl Blob {
+    /// Creates an empty `Blob` with the given root name.
+    pub fn named<S: Into<String>>(name: S) -> Blob {
+        Blob { name: name.into(), fields: Vec::new() }
+    }
+
+    /// The name of the root compound.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Inserts a field into the root compound, overwriting any previous
+    /// value of the same name.
+    pub fn insert<S: Into<String>>(&mut self, name: S, value: Value) {
+        let name = name.into();
+        match self.fields.iter_mut().find(|(n, _)| n == &name) {
+            Some(entry) => entry.1 = value,
+            None => self.fields.push((name, value)),
+        }
+    }
+
+    /// Looks up a field in the root compound by name.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.fields.iter().find(|entry| entry.0 == name).map(|entry| &entry.1)
+    }
+
+    /// Reads a `Blob` from an already-decompressed NBT byte stream, picking
+    /// Java (`Endian::Big`) vs. Bedrock (`Endian::Little`) NBT at the call
+    /// site. Declared lengths are unbounded; see
+    /// [`from_reader_with_limits`](#method.from_reader_with_limits) to guard
+    /// against hostile input.
+    pub fn from_reader_with_endian<R: Read>(src: &mut R, endian: Endian) -> Result<Blob> {
+        Blob::from_reader_with_limits(src, endian, &Limits::none())
+    }
+
+    /// Reads a `Blob`, rejecting any declared array/string length that would
+    /// violate `limits`.
+    pub fn from_reader_with_limits<R: Read>(src: &mut R, endian: Endian, limits: &Limits) -> Result<Blob> {
+        let mut total_read = 0usize;
+
+        let (tag, name) = try!(raw::emit_next_header(src, endian, limits, &mut total_read));
+        if tag != TAG_COMPOUND {
+            return Err(Error::NoRootCompound);
+        }
+
+        let mut blob = Blob::named(name);
+        loop {
+            let (tag, name) = try!(raw::emit_next_header(src, endian, limits, &mut total_read));
+            if tag == TAG_END {
+                break;
+            }
+            let value = try!(Value::read(tag, src, endian, limits, &mut total_read));
+            blob.insert(name, value);
+        }
+
+        Ok(blob)
+    }
+
+    /// Writes this `Blob` to `dst` as an already-decompressed NBT byte
+    /// stream, picking Java (`Endian::Big`) vs. Bedrock (`Endian::Little`)
+    /// NBT at the call site.
+    pub fn write_with_endian<W: Write>(&self, dst: &mut W, endian: Endian) -> Result<()> {
+        try!(raw::write_bare_byte(dst, TAG_COMPOUND as i8));
+        try!(raw::write_bare_string(dst, &self.name, endian));
+
+        for (name, value) in &self.fields {
+            try!(raw::write_bare_byte(dst, value.tag_id() as i8));
+            try!(raw::write_bare_string(dst, name, endian));
+            try!(value.write(dst, endian));
+        }
+
+        raw::close_nbt(dst)
+    }
+
+    /// Reads a `Blob` from `src`, first wrapping it in the decompressor
+    /// selected by `compression` (or none, for `Compression::Uncompressed`).
+    pub fn from_reader_compressed<R: Read>(src: R, endian: Endian, compression: Compression) -> Result<Blob> {
+        match compression {
+            Compression::Uncompressed => {
+                let mut src = src;
+                Blob::from_reader_with_endian(&mut src, endian)
+            }
+            Compression::Gzip => Blob::from_reader_with_endian(&mut GzDecoder::new(src), endian),
+            Compression::Zlib => Blob::from_reader_with_endian(&mut ZlibDecoder::new(src), endian),
+        }
+    }
+
+    /// Reads a gzip-compressed `Blob`, e.g. a Java-edition `.dat` file.
+    pub fn from_gzip_reader<R: Read>(src: R, endian: Endian) -> Result<Blob> {
+        Blob::from_reader_compressed(src, endian, Compression::Gzip)
+    }
+
+    /// Reads a zlib-compressed `Blob`, e.g. a region-file chunk payload.
+    pub fn from_zlib_reader<R: Read>(src: R, endian: Endian) -> Result<Blob> {
+        Blob::from_reader_compressed(src, endian, Compression::Zlib)
+    }
+
+    /// Writes this `Blob` to `dst`, wrapping it in the compressor selected
+    /// by `compression` (or none, for `Compression::Uncompressed`).
+    pub fn write_compressed<W: Write>(&self, dst: W, endian: Endian, compression: Compression) -> Result<()> {
+        match compression {
+            Compression::Uncompressed => {
+                let mut dst = dst;
+                self.write_with_endian(&mut dst, endian)
+            }
+            Compression::Gzip => {
+                let mut encoder = GzEncoder::new(dst, Flate2Compression::default());
+                try!(self.write_with_endian(&mut encoder, endian));
+                try!(encoder.finish());
+                Ok(())
+            }
+            Compression::Zlib => {
+                let mut encoder = ZlibEncoder::new(dst, Flate2Compression::default());
+                try!(self.write_with_endian(&mut encoder, endian));
+                try!(encoder.finish());
+                Ok(())
+            }
+        }
+    }
+
+    /// Writes this `Blob` gzip-compressed, e.g. as a Java-edition `.dat` file.
+    pub fn write_gzip<W: Write>(&self, dst: W, endian: Endian) -> Result<()> {
+        self.write_compressed(dst, endian, Compression::Gzip)
+    }
+
+    /// Writes this `Blob` zlib-compressed, e.g. as a region-file chunk payload.
+    pub fn write_zlib<W: Write>(&self, dst: W, endian: Endian) -> Result<()> {
+        self.write_compressed(dst, endian, Compression::Zlib)
+    }
+}
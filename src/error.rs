@@ -0,0 +1,52 @@
+//! Error handling types for reading and writing NBT data.
+
+use std::fmt;
+use std::io;
+
+/// Errors that can occur during NBT (de)serialization.
+#[derive(Debug)]
+pub enum Error {
+    /// Wraps errors emitted by the underlying `io::Read`/`io::Write`.
+    IoError(io::Error),
+    /// The stream ended before a complete NBT value could be read.
+    IncompleteNbtValue,
+    /// A declared array/string length was negative, which is never valid
+    /// NBT.
+    NegativeLength(i32),
+    /// A declared array/string length exceeded the configured
+    /// [`raw::Limits`](raw/struct.Limits.html).
+    LengthLimitExceeded(usize),
+    /// A string was not valid Modified UTF-8 (MUTF-8).
+    InvalidMutf8,
+    /// Encountered a tag id that does not correspond to any known NBT type,
+    /// or, when reading a `Blob`, a field tag `Blob` does not model
+    /// (`TAG_List` or a nested `TAG_Compound`).
+    InvalidTypeId(u8),
+    /// A `Blob` did not begin with a top-level `TAG_Compound`.
+    NoRootCompound,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::IoError(ref e) => write!(f, "I/O error: {}", e),
+            Error::IncompleteNbtValue => write!(f, "reader ended before a complete NBT value could be read"),
+            Error::NegativeLength(len) => write!(f, "encountered a negative declared length: {}", len),
+            Error::LengthLimitExceeded(len) => write!(f, "declared length {} exceeds the configured limit", len),
+            Error::InvalidMutf8 => write!(f, "encountered invalid Modified UTF-8"),
+            Error::InvalidTypeId(id) => write!(f, "invalid NBT tag id: {}", id),
+            Error::NoRootCompound => write!(f, "NBT blobs must begin with a top-level TAG_Compound"),
+        }
+    }
+}
+
+impl ::std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::IoError(e)
+    }
+}
+
+/// A convenience type alias for `Result`s that use [`Error`](enum.Error.html).
+pub type Result<T> = ::std::result::Result<T, Error>;
@@ -0,0 +1,23 @@
+//! A library for reading and writing the NBT (Named Binary Tag) format used
+//! by Minecraft, for both the Java (big-endian) and Bedrock (little-endian)
+//! editions.
+//!
+//! Most callers want [`Blob`](struct.Blob.html), the high-level type for
+//! reading and writing whole NBT documents. The [`raw`](raw/index.html)
+//! module exposes the lower-level, `Endian`-parameterized primitives that
+//! `Blob` is built on, for callers who want to hand-roll their own encoding.
+
+// This crate predates the `?` operator and still leans on `try!` at most call
+// sites; letting that stand is less churn than rewriting every call site.
+#![allow(deprecated)]
+
+extern crate byteorder;
+extern crate flate2;
+
+pub mod error;
+pub mod raw;
+pub mod blob;
+
+pub use blob::{Blob, Compression, Value};
+pub use error::{Error, Result};
+pub use raw::Endian;
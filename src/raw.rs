@@ -6,13 +6,256 @@
 //!
 //! A high-level API for reading and writing generic NBT data is available in
 //! the [`Blob`](../struct.Blob.html) struct.
+//!
+//! Note: this module only supplies the `Endian`-parameterized primitives,
+//! which only ever see an already-decompressed byte stream. Picking Java
+//! vs. Bedrock NBT at the whole-document level, and gzip/zlib-aware entry
+//! points for compressed `.dat`/region NBT, live on
+//! [`Blob`](../struct.Blob.html) instead.
 
+use std::char;
 use std::io;
+use std::mem;
+use std::slice;
 
-use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use byteorder::{BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
 
 use error::{Error, Result};
 
+/// The byte order of an NBT stream.
+///
+/// Java-edition NBT is big-endian; Bedrock-edition NBT is little-endian.
+/// Every multi-byte primitive in this module is parameterized over `Endian`
+/// so that both dialects can be read and written through the same code path;
+/// the single-byte ones (`read_bare_byte`, `write_bare_byte`, `close_nbt`)
+/// take no `Endian`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    Big,
+    Little,
+}
+
+impl Endian {
+    /// Whether this `Endian` matches the byte order of the host machine.
+    #[inline]
+    fn is_native(self) -> bool {
+        match self {
+            Endian::Big => cfg!(target_endian = "big"),
+            Endian::Little => cfg!(target_endian = "little"),
+        }
+    }
+}
+
+/// Writes a slice of fixed-size integers as a single block, first copying it
+/// into a byte-swapped buffer if `endian` does not match the host's native
+/// byte order.
+/// This avoids the per-element write calls that dominate large `IntArray`/
+/// `LongArray` payloads.
+#[inline]
+fn write_packed<W, T, F>(dst: &mut W, value: &[T], endian: Endian, swap: F) -> Result<()>
+    where W: io::Write, T: Copy, F: Fn(T) -> T
+{
+    let bytes_of = |buf: &[T]| unsafe {
+        slice::from_raw_parts(buf.as_ptr() as *const u8, mem::size_of_val(buf))
+    };
+
+    if endian.is_native() {
+        dst.write_all(bytes_of(value)).map_err(From::from)
+    } else {
+        let swapped: Vec<T> = value.iter().map(|&v| swap(v)).collect();
+        dst.write_all(bytes_of(&swapped)).map_err(From::from)
+    }
+}
+
+/// Limits on the sizes this module's readers will accept before returning
+/// [`Error::LengthLimitExceeded`](../error/enum.Error.html), so that a
+/// hostile length prefix cannot force a huge eager allocation.
+///
+/// `max_array_len` bounds any single declared array/string length;
+/// `max_total_bytes` bounds the cumulative size of all such reads that share
+/// the same running `total_read` counter passed alongside the `Limits`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    pub max_array_len: usize,
+    pub max_total_bytes: usize,
+}
+
+impl Limits {
+    /// A `Limits` that never rejects a read.
+    pub fn none() -> Limits {
+        Limits { max_array_len: usize::MAX, max_total_bytes: usize::MAX }
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Limits { Limits::none() }
+}
+
+/// Reads a declared element count and rejects negative values unconditionally,
+/// before any `Limits` check or allocation. A negative length is never valid
+/// NBT; letting it through as `as usize` would wrap around to a huge value
+/// and defeat the `Limits` guard entirely (`-1i32 as usize == usize::MAX`).
+#[inline]
+fn read_array_len<R>(src: &mut R, endian: Endian) -> Result<usize>
+    where R: io::Read
+{
+    let len = try!(read_bare_int(src, endian));
+    if len < 0 {
+        return Err(Error::NegativeLength(len));
+    }
+    Ok(len as usize)
+}
+
+/// Checks a declared element count against `limits` before any allocation
+/// happens, updating the running `total_read` byte counter on success.
+#[inline]
+fn check_limits(len: usize, elem_size: usize, limits: &Limits, total_read: &mut usize) -> Result<()> {
+    if len > limits.max_array_len {
+        return Err(Error::LengthLimitExceeded(len));
+    }
+    let new_total = total_read.saturating_add(len.saturating_mul(elem_size));
+    if new_total > limits.max_total_bytes {
+        return Err(Error::LengthLimitExceeded(len));
+    }
+    *total_read = new_total;
+    Ok(())
+}
+
+/// Reads `len` fixed-size integers as a single block, byte-swapping in place
+/// afterwards if `endian` does not match the host's native byte order.
+#[inline]
+fn read_packed<R, T, F>(src: &mut R, len: usize, endian: Endian, zero: T, swap: F) -> Result<Vec<T>>
+    where R: io::Read, T: Copy, F: Fn(T) -> T
+{
+    let mut buf = vec![zero; len];
+    {
+        let bytes = unsafe {
+            slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut u8, len * mem::size_of::<T>())
+        };
+        if let Err(e) = src.read_exact(bytes) {
+            return Err(if e.kind() == io::ErrorKind::UnexpectedEof {
+                Error::IncompleteNbtValue
+            } else {
+                From::from(e)
+            });
+        }
+    }
+    if !endian.is_native() {
+        for v in buf.iter_mut() {
+            *v = swap(*v);
+        }
+    }
+    Ok(buf)
+}
+
+/// Encodes `value` as Java's Modified UTF-8 (MUTF-8): the NUL character is
+/// encoded as the two-byte sequence `0xC0 0x80`, and supplementary code
+/// points are split into a CESU-8-style surrogate pair of three-byte
+/// sequences rather than a single four-byte UTF-8 sequence.
+fn encode_mutf8(value: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(value.len());
+
+    for c in value.chars() {
+        let cp = c as u32;
+        if cp == 0 {
+            out.push(0xC0);
+            out.push(0x80);
+        } else if cp <= 0x7F {
+            out.push(cp as u8);
+        } else if cp <= 0x7FF {
+            out.push(0xC0 | (cp >> 6) as u8);
+            out.push(0x80 | (cp & 0x3F) as u8);
+        } else if cp <= 0xFFFF {
+            out.push(0xE0 | (cp >> 12) as u8);
+            out.push(0x80 | ((cp >> 6) & 0x3F) as u8);
+            out.push(0x80 | (cp & 0x3F) as u8);
+        } else {
+            let cp = cp - 0x10000;
+            let hi = 0xD800 + (cp >> 10);
+            let lo = 0xDC00 + (cp & 0x3FF);
+            for surrogate in [hi, lo].iter().cloned() {
+                out.push(0xE0 | (surrogate >> 12) as u8);
+                out.push(0x80 | ((surrogate >> 6) & 0x3F) as u8);
+                out.push(0x80 | (surrogate & 0x3F) as u8);
+            }
+        }
+    }
+
+    out
+}
+
+/// Decodes a Modified UTF-8 (MUTF-8) byte sequence, reassembling `0xC0 0x80`
+/// back into a NUL character and surrogate pairs back into a single
+/// supplementary `char`. Returns `Error::InvalidMutf8` on any malformed
+/// sequence.
+fn decode_mutf8(bytes: &[u8]) -> Result<String> {
+    let mut out = String::with_capacity(bytes.len());
+    let mut i = 0;
+
+    fn continuation(b: u8) -> Result<u32> {
+        if b & 0xC0 == 0x80 { Ok(b as u32 & 0x3F) } else { Err(Error::InvalidMutf8) }
+    }
+
+    while i < bytes.len() {
+        let b0 = bytes[i];
+        if b0 & 0x80 == 0 {
+            out.push(b0 as char);
+            i += 1;
+        } else if b0 & 0xE0 == 0xC0 {
+            if i + 1 >= bytes.len() { return Err(Error::InvalidMutf8); }
+            let b1 = try!(continuation(bytes[i + 1]));
+            if b0 == 0xC0 && bytes[i + 1] == 0x80 {
+                out.push('\0');
+            } else {
+                let cp = ((b0 as u32 & 0x1F) << 6) | b1;
+                // Canonical MUTF-8 only ever emits a 2-byte sequence for NUL
+                // (handled above); every other 2-byte sequence must encode a
+                // code point that actually needs 2 bytes, or it's an overlong
+                // encoding (e.g. `0xC1 0xBF` for `cp == 0x7F`).
+                if cp < 0x80 {
+                    return Err(Error::InvalidMutf8);
+                }
+                out.push(try!(char::from_u32(cp).ok_or(Error::InvalidMutf8)));
+            }
+            i += 2;
+        } else if b0 & 0xF0 == 0xE0 {
+            if i + 2 >= bytes.len() { return Err(Error::InvalidMutf8); }
+            let b1 = try!(continuation(bytes[i + 1]));
+            let b2 = try!(continuation(bytes[i + 2]));
+            let cp = ((b0 as u32 & 0x0F) << 12) | (b1 << 6) | b2;
+
+            if (0xD800..=0xDBFF).contains(&cp) {
+                if i + 5 >= bytes.len() || bytes[i + 3] & 0xF0 != 0xE0 {
+                    return Err(Error::InvalidMutf8);
+                }
+                let b4 = try!(continuation(bytes[i + 4]));
+                let b5 = try!(continuation(bytes[i + 5]));
+                let low = ((bytes[i + 3] as u32 & 0x0F) << 12) | (b4 << 6) | b5;
+                if !(0xDC00..=0xDFFF).contains(&low) { return Err(Error::InvalidMutf8); }
+
+                let combined = 0x10000 + ((cp - 0xD800) << 10) + (low - 0xDC00);
+                out.push(try!(char::from_u32(combined).ok_or(Error::InvalidMutf8)));
+                i += 6;
+            } else {
+                // A 3-byte sequence that encodes a code point of 0x7FF or
+                // below is overlong -- it should have been emitted as a
+                // 1-byte or 2-byte sequence instead (e.g. `0xE0 0x80 0x80`
+                // for `cp == 0`, which this crate's own encoder never
+                // produces).
+                if cp <= 0x7FF {
+                    return Err(Error::InvalidMutf8);
+                }
+                out.push(try!(char::from_u32(cp).ok_or(Error::InvalidMutf8)));
+                i += 3;
+            }
+        } else {
+            return Err(Error::InvalidMutf8);
+        }
+    }
+
+    Ok(out)
+}
+
 /// A convenience function for closing NBT format objects.
 ///
 /// This function writes a single `0x00` byte to the `io::Write` destination,
@@ -31,45 +274,60 @@ pub fn write_bare_byte<W>(dst: &mut W, value: i8) -> Result<()>
 }
 
 #[inline]
-pub fn write_bare_short<W>(dst: &mut W, value: i16) -> Result<()>
+pub fn write_bare_short<W>(dst: &mut W, value: i16, endian: Endian) -> Result<()>
    where W: io::Write
 {
-    dst.write_i16::<LittleEndian>(value).map_err(From::from)
+    match endian {
+        Endian::Big => dst.write_i16::<BigEndian>(value),
+        Endian::Little => dst.write_i16::<LittleEndian>(value),
+    }.map_err(From::from)
 }
 
 #[inline]
-pub fn write_bare_int<W>(dst: &mut W, value: i32) -> Result<()>
+pub fn write_bare_int<W>(dst: &mut W, value: i32, endian: Endian) -> Result<()>
    where W: io::Write
 {
-    dst.write_i32::<LittleEndian>(value).map_err(From::from)
+    match endian {
+        Endian::Big => dst.write_i32::<BigEndian>(value),
+        Endian::Little => dst.write_i32::<LittleEndian>(value),
+    }.map_err(From::from)
 }
 
 #[inline]
-pub fn write_bare_long<W>(dst: &mut W, value: i64) -> Result<()>
+pub fn write_bare_long<W>(dst: &mut W, value: i64, endian: Endian) -> Result<()>
    where W: io::Write
 {
-    dst.write_i64::<LittleEndian>(value).map_err(From::from)
+    match endian {
+        Endian::Big => dst.write_i64::<BigEndian>(value),
+        Endian::Little => dst.write_i64::<LittleEndian>(value),
+    }.map_err(From::from)
 }
 
 #[inline]
-pub fn write_bare_float<W>(dst: &mut W, value: f32) -> Result<()>
+pub fn write_bare_float<W>(dst: &mut W, value: f32, endian: Endian) -> Result<()>
    where W: io::Write
 {
-    dst.write_f32::<LittleEndian>(value).map_err(From::from)
+    match endian {
+        Endian::Big => dst.write_f32::<BigEndian>(value),
+        Endian::Little => dst.write_f32::<LittleEndian>(value),
+    }.map_err(From::from)
 }
 
 #[inline]
-pub fn write_bare_double<W>(dst: &mut W, value: f64) -> Result<()>
+pub fn write_bare_double<W>(dst: &mut W, value: f64, endian: Endian) -> Result<()>
    where W: io::Write
 {
-    dst.write_f64::<LittleEndian>(value).map_err(From::from)
+    match endian {
+        Endian::Big => dst.write_f64::<BigEndian>(value),
+        Endian::Little => dst.write_f64::<LittleEndian>(value),
+    }.map_err(From::from)
 }
 
 #[inline]
-pub fn write_bare_byte_array<W>(dst: &mut W, value: &[i8]) -> Result<()>
+pub fn write_bare_byte_array<W>(dst: &mut W, value: &[i8], endian: Endian) -> Result<()>
    where W: io::Write
 {
-    try!(dst.write_i32::<LittleEndian>(value.len() as i32));
+    try!(write_bare_int(dst, value.len() as i32, endian));
     for &v in value {
         try!(dst.write_i8(v));
     }
@@ -77,40 +335,38 @@ pub fn write_bare_byte_array<W>(dst: &mut W, value: &[i8]) -> Result<()>
 }
 
 #[inline]
-pub fn write_bare_int_array<W>(dst: &mut W, value: &[i32]) -> Result<()>
+pub fn write_bare_int_array<W>(dst: &mut W, value: &[i32], endian: Endian) -> Result<()>
    where W: io::Write
 {
-    try!(dst.write_i32::<LittleEndian>(value.len() as i32));
-    for &v in value {
-        try!(dst.write_i32::<LittleEndian>(v));
-    }
-    Ok(())
+    try!(write_bare_int(dst, value.len() as i32, endian));
+    write_packed(dst, value, endian, i32::swap_bytes)
 }
 
 #[inline]
-pub fn write_bare_long_array<W>(dst: &mut W, value: &[i64]) -> Result<()>
+pub fn write_bare_long_array<W>(dst: &mut W, value: &[i64], endian: Endian) -> Result<()>
    where W: io::Write
 {
-    dst.write_i32::<LittleEndian>(value.len() as i32)?;
-    for &v in value {
-        dst.write_i64::<LittleEndian>(v)?;
-    }
-    Ok(())
+    write_bare_int(dst, value.len() as i32, endian)?;
+    write_packed(dst, value, endian, i64::swap_bytes)
 }
 
 #[inline]
-pub fn write_bare_string<W>(dst: &mut W, value: &str) -> Result<()>
+pub fn write_bare_string<W>(dst: &mut W, value: &str, endian: Endian) -> Result<()>
    where W: io::Write
 {
-    try!(dst.write_u16::<LittleEndian>(value.len() as u16));
-    dst.write_all(value.as_bytes()).map_err(From::from)
+    let bytes = encode_mutf8(value);
+    try!(match endian {
+        Endian::Big => dst.write_u16::<BigEndian>(bytes.len() as u16),
+        Endian::Little => dst.write_u16::<LittleEndian>(bytes.len() as u16),
+    });
+    dst.write_all(&bytes).map_err(From::from)
 }
 
 /// Extracts the next header (tag and name) from an NBT format source.
 ///
 /// This function will also return the `TAG_End` byte and an empty name if it
 /// encounters it.
-pub fn emit_next_header<R>(src: &mut R) -> Result<(u8, String)>
+pub fn emit_next_header<R>(src: &mut R, endian: Endian, limits: &Limits, total_read: &mut usize) -> Result<(u8, String)>
     where R: io::Read
 {
     let tag  = try!(src.read_u8());
@@ -118,7 +374,7 @@ pub fn emit_next_header<R>(src: &mut R) -> Result<(u8, String)>
     match tag {
         0x00 => { Ok((tag, "".to_string())) },
         _    => {
-            let name = try!(read_bare_string(src));
+            let name = try!(read_bare_string(src, endian, limits, total_read));
             Ok((tag, name))
         },
     }
@@ -132,48 +388,63 @@ pub fn read_bare_byte<R>(src: &mut R) -> Result<i8>
 }
 
 #[inline]
-pub fn read_bare_short<R>(src: &mut R) -> Result<i16>
+pub fn read_bare_short<R>(src: &mut R, endian: Endian) -> Result<i16>
     where R: io::Read
 {
-    src.read_i16::<LittleEndian>().map_err(From::from)
+    match endian {
+        Endian::Big => src.read_i16::<BigEndian>(),
+        Endian::Little => src.read_i16::<LittleEndian>(),
+    }.map_err(From::from)
 }
 
 #[inline]
-pub fn read_bare_int<R>(src: &mut R) -> Result<i32>
+pub fn read_bare_int<R>(src: &mut R, endian: Endian) -> Result<i32>
     where R: io::Read
 {
-    src.read_i32::<LittleEndian>().map_err(From::from)
+    match endian {
+        Endian::Big => src.read_i32::<BigEndian>(),
+        Endian::Little => src.read_i32::<LittleEndian>(),
+    }.map_err(From::from)
 }
 
 #[inline]
-pub fn read_bare_long<R>(src: &mut R) -> Result<i64>
+pub fn read_bare_long<R>(src: &mut R, endian: Endian) -> Result<i64>
     where R: io::Read
 {
-    src.read_i64::<LittleEndian>().map_err(From::from)
+    match endian {
+        Endian::Big => src.read_i64::<BigEndian>(),
+        Endian::Little => src.read_i64::<LittleEndian>(),
+    }.map_err(From::from)
 }
 
 #[inline]
-pub fn read_bare_float<R>(src: &mut R) -> Result<f32>
+pub fn read_bare_float<R>(src: &mut R, endian: Endian) -> Result<f32>
     where R: io::Read
 {
-    src.read_f32::<LittleEndian>().map_err(From::from)
+    match endian {
+        Endian::Big => src.read_f32::<BigEndian>(),
+        Endian::Little => src.read_f32::<LittleEndian>(),
+    }.map_err(From::from)
 }
 
 #[inline]
-pub fn read_bare_double<R>(src: &mut R) -> Result<f64>
+pub fn read_bare_double<R>(src: &mut R, endian: Endian) -> Result<f64>
     where R: io::Read
 {
-    src.read_f64::<LittleEndian>().map_err(From::from)
+    match endian {
+        Endian::Big => src.read_f64::<BigEndian>(),
+        Endian::Little => src.read_f64::<LittleEndian>(),
+    }.map_err(From::from)
 }
 
 #[inline]
-pub fn read_bare_byte_array<R>(src: &mut R) -> Result<Vec<i8>>
+pub fn read_bare_byte_array<R>(src: &mut R, endian: Endian, limits: &Limits, total_read: &mut usize) -> Result<Vec<i8>>
     where R: io::Read
 {
-    // FIXME: Is there a way to return [u8; len]?
-    let len = try!(src.read_i32::<LittleEndian>()) as usize;
+    let len = try!(read_array_len(src, endian));
+    try!(check_limits(len, mem::size_of::<i8>(), limits, total_read));
+
     let mut buf = Vec::with_capacity(len);
-    // FIXME: Test performance vs transmute.
     for _ in 0..len {
         buf.push(try!(src.read_i8()));
     }
@@ -181,39 +452,36 @@ pub fn read_bare_byte_array<R>(src: &mut R) -> Result<Vec<i8>>
 }
 
 #[inline]
-pub fn read_bare_int_array<R>(src: &mut R) -> Result<Vec<i32>>
+pub fn read_bare_int_array<R>(src: &mut R, endian: Endian, limits: &Limits, total_read: &mut usize) -> Result<Vec<i32>>
     where R: io::Read
 {
-    // FIXME: Is there a way to return [i32; len]?
-    let len = try!(src.read_i32::<LittleEndian>()) as usize;
-    let mut buf = Vec::with_capacity(len);
-    // FIXME: Test performance vs transmute.
-    for _ in 0..len {
-        buf.push(try!(src.read_i32::<LittleEndian>()));
-    }
-    Ok(buf)
+    let len = try!(read_array_len(src, endian));
+    try!(check_limits(len, mem::size_of::<i32>(), limits, total_read));
+    read_packed(src, len, endian, 0i32, i32::swap_bytes)
 }
 
 #[inline]
-pub fn read_bare_long_array<R>(src: &mut R) -> Result<Vec<i64>>
+pub fn read_bare_long_array<R>(src: &mut R, endian: Endian, limits: &Limits, total_read: &mut usize) -> Result<Vec<i64>>
     where R: io::Read
 {
-    let len = src.read_i32::<LittleEndian>()? as usize;
-    let mut buf = Vec::with_capacity(len);
-    for _ in 0..len {
-        buf.push(src.read_i64::<LittleEndian>()?);
-    }
-    Ok(buf)
+    let len = read_array_len(src, endian)?;
+    check_limits(len, mem::size_of::<i64>(), limits, total_read)?;
+    read_packed(src, len, endian, 0i64, i64::swap_bytes)
 }
 
 #[inline]
-pub fn read_bare_string<R>(src: &mut R) -> Result<String>
+pub fn read_bare_string<R>(src: &mut R, endian: Endian, limits: &Limits, total_read: &mut usize) -> Result<String>
     where R: io::Read
 {
-    let len = try!(src.read_u16::<LittleEndian>()) as usize;
+    let len = try!(match endian {
+        Endian::Big => src.read_u16::<BigEndian>(),
+        Endian::Little => src.read_u16::<LittleEndian>(),
+    }) as usize;
 
     if len == 0 { return Ok("".to_string()); }
 
+    try!(check_limits(len, 1, limits, total_read));
+
     let mut bytes = vec![0; len];
     let mut n_read = 0usize;
     while n_read < bytes.len() {
@@ -223,5 +491,154 @@ pub fn read_bare_string<R>(src: &mut R) -> Result<String>
         }
     }
 
-    String::from_utf8(bytes).map_err(From::from)
+    decode_mutf8(&bytes)
+}
+
+/// Reads `Self` as a single bare NBT value, so that downstream crates can
+/// compose NBT (de)serialization for their own types without going through
+/// the stringly-typed `Blob` API. The blanket impls below simply delegate to
+/// the bare functions in this module, which remain the single source of
+/// truth for the wire encoding.
+pub trait NbtRead: Sized {
+    fn nbt_read<R: io::Read>(src: &mut R, endian: Endian, limits: &Limits, total_read: &mut usize) -> Result<Self>;
+}
+
+/// Writes `self` as a single bare NBT value. See [`NbtRead`](trait.NbtRead.html).
+pub trait NbtWrite {
+    fn nbt_write<W: io::Write>(&self, dst: &mut W, endian: Endian) -> Result<()>;
+}
+
+macro_rules! nbt_primitive_impl {
+    ($ty:ty, $read:ident, $write:ident) => {
+        impl NbtRead for $ty {
+            fn nbt_read<R: io::Read>(src: &mut R, endian: Endian, _limits: &Limits, _total_read: &mut usize) -> Result<$ty> {
+                $read(src, endian)
+            }
+        }
+
+        impl NbtWrite for $ty {
+            fn nbt_write<W: io::Write>(&self, dst: &mut W, endian: Endian) -> Result<()> {
+                $write(dst, *self, endian)
+            }
+        }
+    }
+}
+
+impl NbtRead for i8 {
+    fn nbt_read<R: io::Read>(src: &mut R, _endian: Endian, _limits: &Limits, _total_read: &mut usize) -> Result<i8> {
+        read_bare_byte(src)
+    }
+}
+
+impl NbtWrite for i8 {
+    fn nbt_write<W: io::Write>(&self, dst: &mut W, _endian: Endian) -> Result<()> {
+        write_bare_byte(dst, *self)
+    }
+}
+
+nbt_primitive_impl!(i16, read_bare_short, write_bare_short);
+nbt_primitive_impl!(i32, read_bare_int, write_bare_int);
+nbt_primitive_impl!(i64, read_bare_long, write_bare_long);
+nbt_primitive_impl!(f32, read_bare_float, write_bare_float);
+nbt_primitive_impl!(f64, read_bare_double, write_bare_double);
+
+impl NbtRead for String {
+    fn nbt_read<R: io::Read>(src: &mut R, endian: Endian, limits: &Limits, total_read: &mut usize) -> Result<String> {
+        read_bare_string(src, endian, limits, total_read)
+    }
+}
+
+impl NbtWrite for String {
+    fn nbt_write<W: io::Write>(&self, dst: &mut W, endian: Endian) -> Result<()> {
+        write_bare_string(dst, self, endian)
+    }
+}
+
+// `Vec<T>` is deliberately *not* given a blanket `NbtRead`/`NbtWrite` impl
+// generic over every `T`. NBT only has three array tags -- `TAG_Byte_Array`,
+// `TAG_Int_Array` and `TAG_Long_Array` -- and each one is laid out as a
+// single packed block of its element type, not a length-prefixed sequence of
+// independently (de)serialized values. Routing through `T::nbt_read`/
+// `nbt_write` one element at a time would silently regress the bulk
+// byte-swap path the array readers/writers use, so instead each supported
+// element type is wired directly to its bare array function below.
+macro_rules! nbt_array_impl {
+    ($elem:ty, $read_array:ident, $write_array:ident) => {
+        impl NbtRead for Vec<$elem> {
+            fn nbt_read<R: io::Read>(src: &mut R, endian: Endian, limits: &Limits, total_read: &mut usize) -> Result<Vec<$elem>> {
+                $read_array(src, endian, limits, total_read)
+            }
+        }
+
+        impl NbtWrite for Vec<$elem> {
+            fn nbt_write<W: io::Write>(&self, dst: &mut W, endian: Endian) -> Result<()> {
+                $write_array(dst, self, endian)
+            }
+        }
+    }
+}
+
+nbt_array_impl!(i8, read_bare_byte_array, write_bare_byte_array);
+nbt_array_impl!(i32, read_bare_int_array, write_bare_int_array);
+nbt_array_impl!(i64, read_bare_long_array, write_bare_long_array);
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use error::Error;
+
+    use super::{decode_mutf8, read_bare_int_array, read_bare_long_array, read_bare_string, write_bare_string, Endian, Limits};
+
+    #[test]
+    fn mutf8_string_round_trips_nul_and_astral_char() {
+        let value = "\0 snowman: \u{2603} emoji: \u{1F600}";
+
+        let mut buf = Vec::new();
+        write_bare_string(&mut buf, value, Endian::Big).unwrap();
+
+        let mut cur = Cursor::new(buf);
+        let mut total_read = 0;
+        let decoded = read_bare_string(&mut cur, Endian::Big, &Limits::none(), &mut total_read).unwrap();
+
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn negative_int_array_length_is_rejected() {
+        let mut cur = Cursor::new(vec![0xFFu8, 0xFF, 0xFF, 0xFF]);
+        let mut total_read = 0;
+        let result = read_bare_int_array(&mut cur, Endian::Big, &Limits::none(), &mut total_read);
+        match result {
+            Err(Error::NegativeLength(-1)) => {}
+            other => panic!("expected Err(NegativeLength(-1)), got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn overlong_3_byte_nul_is_rejected() {
+        // `0xE0 0x80 0x80` decodes to code point 0, but canonical MUTF-8
+        // only ever emits NUL as the 2-byte sequence `0xC0 0x80`.
+        let result = decode_mutf8(&[0xE0, 0x80, 0x80]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn overlong_2_byte_sequence_is_rejected() {
+        // `0xC1 0xBF` decodes to code point 0x7F, which should have been
+        // emitted as the single byte `0x7F`.
+        let result = decode_mutf8(&[0xC1, 0xBF]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn negative_long_array_length_is_rejected() {
+        let mut cur = Cursor::new(vec![0xFFu8, 0xFF, 0xFF, 0xFF]);
+        let mut total_read = 0;
+        let result = read_bare_long_array(&mut cur, Endian::Little, &Limits::none(), &mut total_read);
+        match result {
+            Err(Error::NegativeLength(-1)) => {}
+            other => panic!("expected Err(NegativeLength(-1)), got {:?}", other),
+        }
+    }
 }